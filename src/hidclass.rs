@@ -1,8 +1,8 @@
 //! USB HID class definitions.
 
-use super::HidReport;
+use super::{HidReport, HidReportOut, HidReportSet, HidReportSetMember, NoOutputReport};
 use core::marker::PhantomData;
-use usb_device::{class_prelude::*, Result};
+use usb_device::{class_prelude::*, Result, UsbError};
 
 /// This should be used as `device_class` when building the `UsbDevice`.
 pub const USB_CLASS_HID: u8 = 0x03;
@@ -14,6 +14,9 @@ const USB_SUBCLASS_HID: u8 = 0x00;
 /// if the bInterfaceSubClassmember declares that the device supports a boot
 /// interface, otherwise it is 0.
 const USB_PROTOCOL_HID: u8 = 0x00;
+/// bInterfaceSubClass value declaring that the device supports the boot
+/// interface, see [`Hid::new_boot`].
+const USB_SUBCLASS_BOOT: u8 = 0x01;
 
 const HID_VER: [u8; 2] = [0x10, 0x01];
 const HID_COUNTRY_NONE: u8 = 0x00;
@@ -21,6 +24,139 @@ const HID_COUNTRY_NONE: u8 = 0x00;
 const DT_HID: u8 = 0x21;
 const DT_REPORT: u8 = 0x22;
 
+/// Upper bound on a (possibly combined, see [`HidReportSet`]) report
+/// descriptor this crate can serve over GET_DESCRIPTOR.
+const REPORT_DESCRIPTOR_BUF_LEN: usize = 255;
+
+/// bRequest values for HID class-specific control requests, see HID 1.11 §7.2.
+const HID_REQ_GET_REPORT: u8 = 0x01;
+const HID_REQ_GET_IDLE: u8 = 0x02;
+const HID_REQ_GET_PROTOCOL: u8 = 0x03;
+const HID_REQ_SET_REPORT: u8 = 0x09;
+const HID_REQ_SET_IDLE: u8 = 0x0a;
+const HID_REQ_SET_PROTOCOL: u8 = 0x0b;
+
+/// Number of distinct reports [`Hid`] can cache for GET_REPORT, one slot per
+/// member of the largest [`HidReportSet`] this crate supports (a single
+/// report, or a 2-tuple of reports).
+const REPORT_CACHE_SLOTS: usize = 2;
+
+/// Bytes most recently sent via [`Hid::send_report`] for one report, cached
+/// so a later GET_REPORT control request can be answered without re-sending.
+#[derive(Clone, Copy)]
+struct ReportCache {
+    /// `HidReport::REPORT_ID` of the cached report, or `None` if this slot is
+    /// unused or belongs to a device with no report IDs.
+    report_id: Option<u8>,
+    buf: [u8; 64],
+    len: usize,
+}
+
+impl ReportCache {
+    const fn new() -> Self {
+        Self {
+            report_id: None,
+            buf: [0; 64],
+            len: 0,
+        }
+    }
+}
+
+/// Index into a [`ReportCache`] array for the given report ID, allocating an
+/// unused slot on first use.
+///
+/// A device with no report IDs always uses slot 0.
+fn cache_slot_index(cache: &[ReportCache; REPORT_CACHE_SLOTS], report_id: Option<u8>) -> usize {
+    match report_id {
+        None => 0,
+        Some(id) => cache
+            .iter()
+            .position(|slot| slot.report_id == Some(id))
+            .or_else(|| cache.iter().position(|slot| slot.report_id.is_none()))
+            .unwrap_or(0),
+    }
+}
+
+/// Whether `slot` actually holds data for `requested`, as opposed to holding
+/// an unrelated report that merely shares its cache slot (see
+/// [`cache_slot_index`]) or holding nothing at all yet.
+fn cache_slot_matches(slot: &ReportCache, requested: Option<u8>) -> bool {
+    slot.len > 0 && slot.report_id == requested
+}
+
+/// Which of the two output-report sources holds the most recently delivered
+/// data, as tracked by the monotonic sequence numbers each source is
+/// stamped with when data becomes available.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum OutSource {
+    /// The class SET_REPORT control request, with no data pending on the
+    /// interrupt OUT endpoint newer than it.
+    Ctrl,
+    /// The interrupt OUT endpoint, with no pending control request data
+    /// newer than it.
+    Endpoint,
+    /// Neither source has anything pending.
+    None,
+}
+
+fn out_source(ctrl_len: usize, ctrl_seq: u32, ep_len: usize, ep_seq: u32) -> OutSource {
+    match (ctrl_len > 0, ep_len > 0) {
+        (false, false) => OutSource::None,
+        (true, false) => OutSource::Ctrl,
+        (false, true) => OutSource::Endpoint,
+        (true, true) => {
+            if ctrl_seq >= ep_seq {
+                OutSource::Ctrl
+            } else {
+                OutSource::Endpoint
+            }
+        }
+    }
+}
+
+/// Report ID `send_report` should prefix the wire bytes with, or `None` to
+/// send them unprefixed.
+///
+/// The boot protocol has no concept of report IDs: a boot-capable `Hid`
+/// built over a multi-report [`HidReportSet`] must still send the fixed
+/// boot layout with no leading ID byte while [`Protocol::Boot`] is in
+/// effect, even though the same report carries a `REPORT_ID` in the full
+/// report protocol.
+fn wire_report_id(protocol: Protocol, report_id: Option<u8>) -> Option<u8> {
+    match protocol {
+        Protocol::Boot => None,
+        Protocol::Report => report_id,
+    }
+}
+
+/// Boot-protocol device declared by a boot-capable [`Hid`], see HID 1.11 §4.2.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BootDevice {
+    /// bInterfaceProtocol = 1.
+    Keyboard,
+    /// bInterfaceProtocol = 2.
+    Mouse,
+}
+
+impl BootDevice {
+    fn interface_protocol(self) -> u8 {
+        match self {
+            BootDevice::Keyboard => 0x01,
+            BootDevice::Mouse => 0x02,
+        }
+    }
+}
+
+/// Protocol currently in effect on a boot-capable [`Hid`], selected by the
+/// host via the class SET_PROTOCOL request.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    /// Fixed-layout boot protocol understood by a PC BIOS/UEFI.
+    Boot,
+    /// Full report protocol described by `R::DESCRIPTOR`.
+    Report,
+}
+
 /// USB-HID device.
 ///
 /// This is the type-safe driver for human interace devices. To use it, you have to
@@ -48,13 +184,27 @@ const DT_REPORT: u8 = 0x22;
 /// #    const DESCRIPTOR: &'static [u8] = &[];
 /// # }
 /// ```
-pub struct Hid<'a, R: HidReport, B: UsbBus> {
+pub struct Hid<'a, R: HidReportSet, B: UsbBus, O: HidReportOut = NoOutputReport> {
     data_if: InterfaceNumber,
     write_ep: EndpointIn<'a, B>,
+    read_ep: Option<EndpointOut<'a, B>>,
+    idle: u8,
+    idle_report_id: u8,
+    ctrl_out_buf: [u8; 64],
+    ctrl_out_len: usize,
+    ctrl_out_seq: u32,
+    out_ep_buf: [u8; 64],
+    out_ep_len: usize,
+    out_ep_seq: u32,
+    next_out_seq: u32,
+    report_cache: [ReportCache; REPORT_CACHE_SLOTS],
+    boot_device: Option<BootDevice>,
+    protocol: Protocol,
     _report: PhantomData<&'a R>,
+    _report_out: PhantomData<O>,
 }
 
-impl<'a, R: HidReport, B: UsbBus> Hid<'a, R, B> {
+impl<'a, R: HidReportSet, B: UsbBus> Hid<'a, R, B, NoOutputReport> {
     /// Create a new `Hid` for the given USB allocator.
     ///
     /// `poll_ms` is the period of host poloing for the reports, milliseconds.
@@ -65,7 +215,79 @@ impl<'a, R: HidReport, B: UsbBus> Hid<'a, R, B> {
         Self {
             data_if: alloc.interface(),
             write_ep: alloc.interrupt(64, poll_ms),
+            read_ep: None,
+            idle: 0,
+            idle_report_id: 0,
+            ctrl_out_buf: [0; 64],
+            ctrl_out_len: 0,
+            ctrl_out_seq: 0,
+            out_ep_buf: [0; 64],
+            out_ep_len: 0,
+            out_ep_seq: 0,
+            next_out_seq: 0,
+            report_cache: [ReportCache::new(); REPORT_CACHE_SLOTS],
+            boot_device: None,
+            protocol: Protocol::Report,
+            _report: PhantomData,
+            _report_out: PhantomData,
+        }
+    }
+
+    /// Create a new `Hid` that advertises a boot interface.
+    ///
+    /// This sets bInterfaceSubClass/bInterfaceProtocol so the device can be
+    /// used by a PC BIOS/UEFI before the report descriptor is parsed. The
+    /// host selects between the boot and report protocol at runtime with
+    /// GET_PROTOCOL/SET_PROTOCOL; see [`Hid::protocol`] and
+    /// [`HidReport::as_boot_report`].
+    pub fn new_boot(alloc: &'a UsbBusAllocator<B>, poll_ms: u8, boot_device: BootDevice) -> Self {
+        Self {
+            data_if: alloc.interface(),
+            write_ep: alloc.interrupt(64, poll_ms),
+            read_ep: None,
+            idle: 0,
+            idle_report_id: 0,
+            ctrl_out_buf: [0; 64],
+            ctrl_out_len: 0,
+            ctrl_out_seq: 0,
+            out_ep_buf: [0; 64],
+            out_ep_len: 0,
+            out_ep_seq: 0,
+            next_out_seq: 0,
+            report_cache: [ReportCache::new(); REPORT_CACHE_SLOTS],
+            boot_device: Some(boot_device),
+            protocol: Protocol::Report,
             _report: PhantomData,
+            _report_out: PhantomData,
+        }
+    }
+}
+
+impl<'a, R: HidReportSet, B: UsbBus, O: HidReportOut> Hid<'a, R, B, O> {
+    /// Create a new `Hid` with an interrupt OUT endpoint for output reports.
+    ///
+    /// Use this instead of [`Hid::new`] when the device needs to receive
+    /// output reports from the host, such as keyboard LED state. `poll_ms`
+    /// has the same meaning as in `new`.
+    pub fn new_with_output(alloc: &'a UsbBusAllocator<B>, poll_ms: u8) -> Self {
+        Self {
+            data_if: alloc.interface(),
+            write_ep: alloc.interrupt(64, poll_ms),
+            read_ep: Some(alloc.interrupt_out(64, poll_ms)),
+            idle: 0,
+            idle_report_id: 0,
+            ctrl_out_buf: [0; 64],
+            ctrl_out_len: 0,
+            ctrl_out_seq: 0,
+            out_ep_buf: [0; 64],
+            out_ep_len: 0,
+            out_ep_seq: 0,
+            next_out_seq: 0,
+            report_cache: [ReportCache::new(); REPORT_CACHE_SLOTS],
+            boot_device: None,
+            protocol: Protocol::Report,
+            _report: PhantomData,
+            _report_out: PhantomData,
         }
     }
 
@@ -73,29 +295,148 @@ impl<'a, R: HidReport, B: UsbBus> Hid<'a, R, B> {
     ///
     /// This function sends HID report to the host as soon as possible.
     /// It converts report to bytes using `AsRef<[u8]>`. Result of the
-    /// conversion MUST match the format described in `R::DESCRIPTOR`.
-    pub fn send_report(&mut self, report: &R) -> Result<usize> {
-        self.write_ep.write(report.as_ref())
+    /// conversion MUST match the format described in `T::DESCRIPTOR`, unless
+    /// the boot protocol is in effect, in which case `T::as_boot_report` is
+    /// sent instead.
+    ///
+    /// `T` is any individual report, not necessarily `R` itself: when `R` is
+    /// a [`HidReportSet`] combining several reports (e.g. a keyboard+mouse
+    /// combo), call this once per report type and the bytes are prefixed
+    /// with `T::REPORT_ID` so the host can tell them apart, unless the boot
+    /// protocol is in effect, which has no report IDs of its own.
+    ///
+    /// A copy of `bytes` (without the report ID prefix) is kept so a later
+    /// GET_REPORT control request can be answered without re-sending.
+    pub fn send_report<T: HidReport>(&mut self, report: &T) -> Result<usize>
+    where
+        R: HidReportSetMember<T>,
+    {
+        let bytes = match self.protocol {
+            Protocol::Report => report.as_ref(),
+            Protocol::Boot => report.as_boot_report(),
+        };
+        let slot = &mut self.report_cache[self.report_cache_slot(T::REPORT_ID)];
+        slot.report_id = T::REPORT_ID;
+        let cached_len = bytes.len().min(slot.buf.len());
+        slot.buf[..cached_len].copy_from_slice(&bytes[..cached_len]);
+        slot.len = cached_len;
+
+        match wire_report_id(self.protocol, T::REPORT_ID) {
+            Some(id) => {
+                let mut buf = [0u8; 65];
+                let len = bytes.len().min(buf.len() - 1);
+                buf[0] = id;
+                buf[1..=len].copy_from_slice(&bytes[..len]);
+                self.write_ep.write(&buf[..=len])
+            }
+            None => self.write_ep.write(bytes),
+        }
+    }
+
+    /// Protocol currently selected by the host via SET_PROTOCOL.
+    ///
+    /// Always [`Protocol::Report`] unless this `Hid` was created with
+    /// [`Hid::new_boot`].
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    /// Receive an output report.
+    ///
+    /// Returns the report most recently delivered either over the interrupt
+    /// OUT endpoint or via the class SET_REPORT control request, whichever
+    /// this crate observed last. Returns `Err(UsbError::WouldBlock)` if
+    /// neither source has anything pending.
+    pub fn receive_report(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.out_ep_len == 0 {
+            if let Some(ep) = &mut self.read_ep {
+                match ep.read(&mut self.out_ep_buf) {
+                    Ok(len) => {
+                        self.out_ep_len = len;
+                        self.out_ep_seq = self.bump_out_seq();
+                    }
+                    Err(UsbError::WouldBlock) => (),
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        match out_source(self.ctrl_out_len, self.ctrl_out_seq, self.out_ep_len, self.out_ep_seq) {
+            OutSource::Ctrl => {
+                let len = self.ctrl_out_len;
+                buf[..len].copy_from_slice(&self.ctrl_out_buf[..len]);
+                self.ctrl_out_len = 0;
+                Ok(len)
+            }
+            OutSource::Endpoint => {
+                let len = self.out_ep_len;
+                buf[..len].copy_from_slice(&self.out_ep_buf[..len]);
+                self.out_ep_len = 0;
+                Ok(len)
+            }
+            OutSource::None => Err(UsbError::WouldBlock),
+        }
+    }
+
+    /// Idle period requested by the host via SET_IDLE, in milliseconds.
+    ///
+    /// `0` means the host wants reports only when the data changes
+    /// (indefinite idle). A caller that already sent the current report
+    /// within this period may skip re-sending an unchanged one, which is
+    /// the intended semantics of HID idle.
+    pub fn idle_period_ms(&self) -> u32 {
+        u32::from(self.idle) * 4
+    }
+
+    /// Report ID the last SET_IDLE request applied to, or `0` if the device
+    /// does not use report IDs.
+    pub fn idle_report_id(&self) -> u8 {
+        self.idle_report_id
+    }
+
+    /// Index into `report_cache` for the given report ID. Shared by
+    /// `send_report` and the GET_REPORT handler so both always agree on
+    /// which slot a report ID maps to.
+    fn report_cache_slot(&self, report_id: Option<u8>) -> usize {
+        cache_slot_index(&self.report_cache, report_id)
+    }
+
+    /// Advance and return the sequence counter used to order the two output
+    /// report sources in [`Hid::receive_report`].
+    fn bump_out_seq(&mut self) -> u32 {
+        self.next_out_seq = self.next_out_seq.wrapping_add(1);
+        self.next_out_seq
     }
 }
 
-impl<R: HidReport, B: UsbBus> Hid<'_, R, B> {
+impl<R: HidReportSet, B: UsbBus, O: HidReportOut> Hid<'_, R, B, O> {
+    /// Compile-time check that `R`'s combined report descriptor fits in the
+    /// fixed-size buffer `get_descriptor` serves it from. Add a report to a
+    /// [`HidReportSet`] too large for `REPORT_DESCRIPTOR_BUF_LEN` and this
+    /// `Hid` fails to monomorphize instead of panicking at runtime.
+    const DESCRIPTOR_FITS: () = assert!(
+        R::DESCRIPTOR_LEN <= REPORT_DESCRIPTOR_BUF_LEN,
+        "combined HID report descriptor exceeds REPORT_DESCRIPTOR_BUF_LEN",
+    );
+
     fn get_descriptor(&self, xfer: ControlIn<B>) {
+        let () = Self::DESCRIPTOR_FITS;
         let (ty, idx) = xfer.request().descriptor_type_index();
         if ty == DT_REPORT && idx == 0 {
-            xfer.accept_with_static(R::DESCRIPTOR).ok();
+            let mut buf = [0u8; REPORT_DESCRIPTOR_BUF_LEN];
+            let len = R::write_descriptor(&mut buf);
+            xfer.accept_with(&buf[..len]).ok();
         }
     }
 }
 
-impl<R: HidReport, B: UsbBus> UsbClass<B> for Hid<'_, R, B> {
+impl<R: HidReportSet, B: UsbBus, O: HidReportOut> UsbClass<B> for Hid<'_, R, B, O> {
     fn get_configuration_descriptors(&self, writer: &mut DescriptorWriter) -> Result<()> {
-        writer.interface(
-            self.data_if,
-            USB_CLASS_HID,
-            USB_SUBCLASS_HID,
-            USB_PROTOCOL_HID,
-        )?;
+        let (sub_class, protocol) = match self.boot_device {
+            Some(boot_device) => (USB_SUBCLASS_BOOT, boot_device.interface_protocol()),
+            None => (USB_SUBCLASS_HID, USB_PROTOCOL_HID),
+        };
+        writer.interface(self.data_if, USB_CLASS_HID, sub_class, protocol)?;
 
         writer.write(
             DT_HID,
@@ -104,12 +445,15 @@ impl<R: HidReport, B: UsbBus> UsbClass<B> for Hid<'_, R, B> {
                 HID_COUNTRY_NONE,       // bCountryCode
                 0x01,                   // bNumDescriptors
                 DT_REPORT,              // bDescriptorType
-                R::DESCRIPTOR.len() as u8,
-                (R::DESCRIPTOR.len() >> 8) as u8, // wDescriptorLength
+                R::DESCRIPTOR_LEN as u8,
+                (R::DESCRIPTOR_LEN >> 8) as u8, // wDescriptorLength
             ],
         )?;
 
         writer.endpoint(&self.write_ep)?;
+        if let Some(read_ep) = &self.read_ep {
+            writer.endpoint(read_ep)?;
+        }
 
         Ok(())
     }
@@ -127,7 +471,160 @@ impl<R: HidReport, B: UsbBus> UsbClass<B> for Hid<'_, R, B> {
             (control::RequestType::Standard, control::Request::GET_DESCRIPTOR) => {
                 self.get_descriptor(xfer)
             }
+            (control::RequestType::Class, HID_REQ_GET_REPORT) => {
+                // wValue high byte is the Report Type, low byte the Report ID (0 if unused).
+                let report_id = req.value as u8;
+                let requested = if report_id == 0 { None } else { Some(report_id) };
+                let slot = &self.report_cache[self.report_cache_slot(requested)];
+                let cached = cache_slot_matches(slot, requested).then_some(slot);
+                match requested {
+                    Some(id) => {
+                        let mut buf = [0u8; 65];
+                        buf[0] = id;
+                        if let Some(slot) = cached {
+                            buf[1..=slot.len].copy_from_slice(&slot.buf[..slot.len]);
+                            xfer.accept_with(&buf[..=slot.len]).ok();
+                        } else {
+                            xfer.accept_with(&buf).ok();
+                        }
+                    }
+                    None => match cached {
+                        Some(slot) => {
+                            xfer.accept_with(&slot.buf[..slot.len]).ok();
+                        }
+                        None => {
+                            xfer.accept_with(&[0u8; 64]).ok();
+                        }
+                    },
+                }
+            }
+            (control::RequestType::Class, HID_REQ_GET_IDLE) => {
+                xfer.accept_with(&[self.idle]).ok();
+            }
+            (control::RequestType::Class, HID_REQ_GET_PROTOCOL) => {
+                let protocol = match self.protocol {
+                    Protocol::Boot => 0x00,
+                    Protocol::Report => 0x01,
+                };
+                xfer.accept_with(&[protocol]).ok();
+            }
+            _ => (),
+        }
+    }
+
+    fn control_out(&mut self, xfer: ControlOut<B>) {
+        let req = xfer.request();
+
+        if !(req.recipient == control::Recipient::Interface
+            && req.index == u8::from(self.data_if) as u16)
+        {
+            return;
+        }
+
+        match (req.request_type, req.request) {
+            (control::RequestType::Class, HID_REQ_SET_IDLE) => {
+                self.idle = (req.value >> 8) as u8;
+                self.idle_report_id = req.value as u8;
+                xfer.accept().ok();
+            }
+            (control::RequestType::Class, HID_REQ_SET_REPORT) => {
+                let data = xfer.data();
+                let len = data.len().min(self.ctrl_out_buf.len());
+                self.ctrl_out_buf[..len].copy_from_slice(&data[..len]);
+                self.ctrl_out_len = len;
+                self.ctrl_out_seq = self.bump_out_seq();
+                xfer.accept().ok();
+            }
+            (control::RequestType::Class, HID_REQ_SET_PROTOCOL) if self.boot_device.is_some() => {
+                self.protocol = if req.value == 0 {
+                    Protocol::Boot
+                } else {
+                    Protocol::Report
+                };
+                xfer.accept().ok();
+            }
             _ => (),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slot(report_id: Option<u8>, len: usize) -> ReportCache {
+        ReportCache {
+            report_id,
+            buf: [0; 64],
+            len,
+        }
+    }
+
+    #[test]
+    fn cache_slot_index_with_no_report_id_always_uses_slot_0() {
+        let cache = [ReportCache::new(); REPORT_CACHE_SLOTS];
+        assert_eq!(cache_slot_index(&cache, None), 0);
+    }
+
+    #[test]
+    fn cache_slot_index_reuses_the_slot_already_holding_that_id() {
+        let cache = [slot(Some(1), 4), slot(Some(2), 4)];
+        assert_eq!(cache_slot_index(&cache, Some(2)), 1);
+    }
+
+    #[test]
+    fn cache_slot_index_allocates_the_first_unused_slot() {
+        let cache = [slot(Some(1), 4), ReportCache::new()];
+        assert_eq!(cache_slot_index(&cache, Some(2)), 1);
+    }
+
+    #[test]
+    fn cache_slot_index_falls_back_to_slot_0_when_both_slots_are_taken() {
+        let cache = [slot(Some(1), 4), slot(Some(2), 4)];
+        assert_eq!(cache_slot_index(&cache, Some(3)), 0);
+    }
+
+    #[test]
+    fn cache_slot_matches_requires_the_same_report_id() {
+        let occupied = slot(Some(1), 4);
+        assert!(cache_slot_matches(&occupied, Some(1)));
+        assert!(!cache_slot_matches(&occupied, Some(3)));
+    }
+
+    #[test]
+    fn cache_slot_matches_requires_data_to_be_present() {
+        let empty = slot(Some(1), 0);
+        assert!(!cache_slot_matches(&empty, Some(1)));
+    }
+
+    #[test]
+    fn cache_slot_matches_with_no_report_ids() {
+        let occupied = slot(None, 4);
+        assert!(cache_slot_matches(&occupied, None));
+    }
+
+    #[test]
+    fn out_source_picks_whichever_side_has_the_higher_sequence_number() {
+        assert_eq!(out_source(0, 0, 0, 0), OutSource::None);
+        assert_eq!(out_source(4, 1, 0, 0), OutSource::Ctrl);
+        assert_eq!(out_source(0, 0, 4, 1), OutSource::Endpoint);
+        assert_eq!(out_source(4, 1, 4, 2), OutSource::Endpoint);
+        assert_eq!(out_source(4, 2, 4, 1), OutSource::Ctrl);
+        // A tie favors Ctrl, since control_out stamps its sequence number
+        // synchronously while receive_report only observes the endpoint's
+        // arrival on its next poll.
+        assert_eq!(out_source(4, 2, 4, 2), OutSource::Ctrl);
+    }
+
+    #[test]
+    fn wire_report_id_is_suppressed_in_boot_protocol() {
+        assert_eq!(wire_report_id(Protocol::Boot, Some(1)), None);
+        assert_eq!(wire_report_id(Protocol::Boot, None), None);
+    }
+
+    #[test]
+    fn wire_report_id_passes_through_in_report_protocol() {
+        assert_eq!(wire_report_id(Protocol::Report, Some(1)), Some(1));
+        assert_eq!(wire_report_id(Protocol::Report, None), None);
+    }
+}
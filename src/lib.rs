@@ -1,4 +1,4 @@
-#![no_std]
+#![cfg_attr(not(test), no_std)]
 
 //! USB Human Interface Device (HID) support for microcontrollers based on `usb-device`.
 //!
@@ -9,7 +9,7 @@
 
 mod hidclass;
 
-pub use hidclass::{Hid, USB_CLASS_HID};
+pub use hidclass::{BootDevice, Hid, Protocol, USB_CLASS_HID};
 
 /// Trait for types that can be used as HID reports.
 ///
@@ -72,4 +72,97 @@ pub trait HidReport: AsRef<[u8]> {
     /// The complete manual for HID report descriptors can be found at
     /// [https://www.usb.org/document-library/hid-usage-tables-112]
     const DESCRIPTOR: &'static [u8];
+
+    /// Report ID this report is prefixed with, for devices that combine
+    /// several reports on one interface (see [`HidReportSet`]).
+    ///
+    /// Leave as `None` for a device with a single report and no report ID
+    /// byte, which is the common case.
+    const REPORT_ID: Option<u8> = None;
+
+    /// Report bytes to send while the USB boot protocol (see
+    /// [`Hid::new_boot`](hidclass::Hid::new_boot)) is in effect.
+    ///
+    /// The default implementation returns the same bytes as the full
+    /// report; override it for a boot keyboard or boot mouse to produce
+    /// the fixed layout a PC BIOS/UEFI expects instead.
+    fn as_boot_report(&self) -> &[u8] {
+        self.as_ref()
+    }
 }
+
+/// One or more [`HidReport`] types sharing a single [`Hid`](hidclass::Hid).
+///
+/// Implemented for every `R: HidReport` (a single report), and for tuples of
+/// two `HidReport` types whose report descriptors are concatenated into one
+/// combined report descriptor, e.g. a keyboard+mouse combo distinguished by
+/// `HidReport::REPORT_ID` on each member.
+pub trait HidReportSet {
+    /// Combined length of the report descriptor(s) in this set, in bytes.
+    const DESCRIPTOR_LEN: usize;
+
+    /// Write the combined report descriptor into `buf`, returning the
+    /// number of bytes written.
+    ///
+    /// `buf` must be at least `DESCRIPTOR_LEN` bytes long.
+    fn write_descriptor(buf: &mut [u8]) -> usize;
+}
+
+impl<R: HidReport> HidReportSet for R {
+    const DESCRIPTOR_LEN: usize = R::DESCRIPTOR.len();
+
+    fn write_descriptor(buf: &mut [u8]) -> usize {
+        let len = R::DESCRIPTOR.len();
+        buf[..len].copy_from_slice(R::DESCRIPTOR);
+        len
+    }
+}
+
+impl<A: HidReport, B: HidReport> HidReportSet for (A, B) {
+    const DESCRIPTOR_LEN: usize = A::DESCRIPTOR.len() + B::DESCRIPTOR.len();
+
+    fn write_descriptor(buf: &mut [u8]) -> usize {
+        let a_len = A::DESCRIPTOR.len();
+        let b_len = B::DESCRIPTOR.len();
+        buf[..a_len].copy_from_slice(A::DESCRIPTOR);
+        buf[a_len..a_len + b_len].copy_from_slice(B::DESCRIPTOR);
+        a_len + b_len
+    }
+}
+
+/// Restricts `T` to a report actually described by a [`HidReportSet`]: either
+/// `R` itself (single-report case) or one of the reports making up a tuple
+/// `R`.
+///
+/// [`Hid::send_report`](hidclass::Hid::send_report) is bounded by this trait
+/// so it rejects, at compile time, a report whose `DESCRIPTOR` was never
+/// advertised by this `Hid`'s `get_configuration_descriptors`.
+pub trait HidReportSetMember<T: HidReport>: HidReportSet {}
+
+impl<R: HidReport> HidReportSetMember<R> for R {}
+
+impl<A: HidReport, B: HidReport> HidReportSetMember<A> for (A, B) {}
+impl<A: HidReport, B: HidReport> HidReportSetMember<B> for (A, B) {}
+
+/// Trait for types that can receive HID output reports (e.g. keyboard LED state).
+///
+/// Unlike [`HidReport`], an output report contributes no descriptor of its
+/// own: the OUTPUT items describing it belong in the same report descriptor
+/// as the input report they accompany, written by hand as part of
+/// `R::DESCRIPTOR`. This trait only names the output report type;
+/// [`Hid::receive_report`](hidclass::Hid::receive_report) copies the
+/// received bytes into a caller-supplied `&mut [u8]`.
+pub trait HidReportOut: AsMut<[u8]> {}
+
+/// Placeholder output report used by [`Hid`](hidclass::Hid) when the device
+/// has no output reports.
+#[derive(Default)]
+pub struct NoOutputReport;
+
+impl AsMut<[u8]> for NoOutputReport {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut []
+    }
+}
+
+impl HidReportOut for NoOutputReport {}